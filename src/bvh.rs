@@ -33,31 +33,33 @@ impl<'a, 'm> BVH<'a, 'm> {
         internal: &mut Vec<Internal>,
         leaf: &mut Vec<Leaf>,
     ) -> ChildPointer {
-        let mut bounding = AABB::zero();
-        for surface in surfaces.iter() {
+        // Seed from the first surface rather than `AABB::zero()`: unioning an
+        // empty box at the origin would otherwise drag every node's bounds
+        // toward (0, 0, 0) whenever the scene doesn't straddle it.
+        let mut bounding = surfaces[0].aabb();
+        for surface in surfaces.iter().skip(1) {
             bounding.union_mut(&surface.aabb());
         }
-        if surfaces.len() <= 2 {
-            let idx = leaf.len();
-            leaf.push(Leaf {
-                begin: surface_index,
-                length: surfaces.len(),
-                bounding,
-            });
-            ChildPointer::Leaf(idx)
+
+        let n = surfaces.len();
+        let leaf_cost = bounding.surface_area() * n as f32;
+        let split = if n > 2 {
+            BVH::find_sah_split(surfaces, &bounding).filter(|s| s.cost < leaf_cost)
         } else {
-            let axis = bounding.max_axis();
+            None
+        };
 
+        if let Some(split) = split {
             surfaces.sort_unstable_by(|a, b| {
-                a.aabb().midpoint()[axis]
-                    .partial_cmp(&b.aabb().midpoint()[axis])
+                a.aabb().midpoint()[split.axis]
+                    .partial_cmp(&b.aabb().midpoint()[split.axis])
                     .unwrap()
             });
-            let (mut l, mut r) = surfaces.split_at_mut(surfaces.len() / 2);
+            let (l, r) = surfaces.split_at_mut(split.count);
 
             let ll = l.len();
-            let ln = BVH::new_recur(&mut l, surface_index, internal, leaf);
-            let rn = BVH::new_recur(&mut r, surface_index + ll, internal, leaf);
+            let ln = BVH::new_recur(l, surface_index, internal, leaf);
+            let rn = BVH::new_recur(r, surface_index + ll, internal, leaf);
 
             let idx = internal.len();
             internal.push(Internal {
@@ -66,41 +68,143 @@ impl<'a, 'm> BVH<'a, 'm> {
                 bounding,
             });
             ChildPointer::Internal(idx)
+        } else {
+            let idx = leaf.len();
+            leaf.push(Leaf {
+                begin: surface_index,
+                length: n,
+                bounding,
+            });
+            ChildPointer::Leaf(idx)
+        }
+    }
+
+    // Bins centroids into SAH_BINS buckets per axis and sweeps prefix/suffix
+    // surface areas instead of evaluating every split position exactly.
+    fn find_sah_split(surfaces: &[CanHit<'m>], bounding: &AABB) -> Option<SahSplit> {
+        const SAH_BINS: usize = 12;
+
+        let mut best: Option<SahSplit> = None;
+        for axis in 0..3 {
+            let axis_min = bounding.min()[axis];
+            let extent = bounding.max()[axis] - axis_min;
+            if extent <= 0.0 {
+                continue;
+            }
+
+            let mut bin_count = [0usize; SAH_BINS];
+            let mut bin_bounds: [Option<AABB>; SAH_BINS] = Default::default();
+            for surface in surfaces.iter() {
+                let aabb = surface.aabb();
+                let centroid = aabb.midpoint()[axis];
+                let bin = (((centroid - axis_min) / extent) * SAH_BINS as f32) as usize;
+                let bin = bin.min(SAH_BINS - 1);
+                bin_count[bin] += 1;
+                match &mut bin_bounds[bin] {
+                    Some(bounds) => bounds.union_mut(&aabb),
+                    None => bin_bounds[bin] = Some(aabb),
+                }
+            }
+
+            let mut left_area = [0.0f32; SAH_BINS];
+            let mut left_count = [0usize; SAH_BINS];
+            let mut running_bounds: Option<AABB> = None;
+            let mut running_count = 0;
+            for i in 0..SAH_BINS {
+                if bin_count[i] > 0 {
+                    match &mut running_bounds {
+                        Some(bounds) => bounds.union_mut(bin_bounds[i].as_ref().unwrap()),
+                        None => running_bounds = bin_bounds[i].clone(),
+                    }
+                    running_count += bin_count[i];
+                }
+                left_area[i] = running_bounds.as_ref().map_or(0.0, AABB::surface_area);
+                left_count[i] = running_count;
+            }
+
+            let mut right_area = [0.0f32; SAH_BINS];
+            let mut right_count = [0usize; SAH_BINS];
+            let mut running_bounds: Option<AABB> = None;
+            let mut running_count = 0;
+            for i in (0..SAH_BINS).rev() {
+                if bin_count[i] > 0 {
+                    match &mut running_bounds {
+                        Some(bounds) => bounds.union_mut(bin_bounds[i].as_ref().unwrap()),
+                        None => running_bounds = bin_bounds[i].clone(),
+                    }
+                    running_count += bin_count[i];
+                }
+                right_area[i] = running_bounds.as_ref().map_or(0.0, AABB::surface_area);
+                right_count[i] = running_count;
+            }
+
+            for i in 0..(SAH_BINS - 1) {
+                let n_l = left_count[i];
+                let n_r = right_count[i + 1];
+                if n_l == 0 || n_r == 0 {
+                    continue;
+                }
+                let cost = left_area[i] * n_l as f32 + right_area[i + 1] * n_r as f32;
+                if best.as_ref().map_or(true, |b| cost < b.cost) {
+                    best = Some(SahSplit {
+                        axis,
+                        count: n_l,
+                        cost,
+                    });
+                }
+            }
         }
+        best
     }
 
-    pub fn ray_intersect(&'m self, ray: &Ray) -> Option<(f32, &'a dyn Geometry<'m>)> {
-        self.ray_intersect_walk(ray, &ChildPointer::Internal(self.root))
+    pub fn ray_intersect(&'m self, ray: &Ray) -> Option<(f32, f32, f32, &'a dyn Geometry<'m>)> {
+        self.ray_intersect_walk(ray, &ChildPointer::Internal(self.root), None)
     }
 
     fn ray_intersect_walk(
         &'m self,
         ray: &Ray,
         p: &ChildPointer,
-    ) -> Option<(f32, &'a dyn Geometry<'m>)> {
+        t_max: Option<f32>,
+    ) -> Option<(f32, f32, f32, &'a dyn Geometry<'m>)> {
         match p {
             ChildPointer::Internal(i) => {
                 let node = &self.internal[*i];
-                if !node.bounding.ray_hit(ray) {
+                let near = node.bounding.ray_hit(ray)?;
+                if t_max.is_some_and(|t_max| near > t_max) {
                     return None;
                 }
-                self.ray_intersect_walk(ray, &node.left)
-                    .or_else(|| self.ray_intersect_walk(ray, &node.right))
+                let left = self.ray_intersect_walk(ray, &node.left, t_max);
+                let t_max = match (&left, t_max) {
+                    (Some((t, _, _, _)), Some(t_max)) => Some(t.min(t_max)),
+                    (Some((t, _, _, _)), None) => Some(*t),
+                    (None, t_max) => t_max,
+                };
+                let right = self.ray_intersect_walk(ray, &node.right, t_max);
+                match (left, right) {
+                    (Some(l), Some(r)) => Some(if l.0 < r.0 { l } else { r }),
+                    (Some(l), None) => Some(l),
+                    (None, right) => right,
+                }
             }
             ChildPointer::Leaf(l) => {
                 let leaf = &self.leaf[*l];
-                if !leaf.bounding.ray_hit(ray) {
+                let near = leaf.bounding.ray_hit(ray)?;
+                if t_max.is_some_and(|t_max| near > t_max) {
                     return None;
                 }
                 let mut best_hit = None;
                 for surf in self.surfaces[leaf.begin..(leaf.begin + leaf.length)].iter() {
-                    if let Some((t, geom)) = surf.ray_intersect(&ray) {
-                        if let Some((prior_t, _)) = best_hit {
+                    if let Some((t, u, v, geom)) = surf.ray_intersect(&ray) {
+                        if t_max.is_some_and(|t_max| t > t_max) {
+                            continue;
+                        }
+                        if let Some((prior_t, _, _, _)) = best_hit {
                             if t < prior_t {
-                                best_hit = Some((t, geom));
+                                best_hit = Some((t, u, v, geom));
                             }
                         } else {
-                            best_hit = Some((t, geom));
+                            best_hit = Some((t, u, v, geom));
                         }
                     }
                 }
@@ -117,14 +221,14 @@ impl<'a, 'm> BVH<'a, 'm> {
         match p {
             ChildPointer::Internal(i) => {
                 let node = &self.internal[*i];
-                if !node.bounding.ray_hit(ray) {
+                if node.bounding.ray_hit(ray).is_none() {
                     return false;
                 }
                 self.hits_any_walk(ray, &node.left) || self.hits_any_walk(ray, &node.right)
             }
             ChildPointer::Leaf(l) => {
                 let leaf = &self.leaf[*l];
-                if !leaf.bounding.ray_hit(ray) {
+                if leaf.bounding.ray_hit(ray).is_none() {
                     return false;
                 }
                 for surf in self.surfaces[leaf.begin..(leaf.begin + leaf.length)].iter() {
@@ -154,4 +258,60 @@ pub struct Leaf {
 enum ChildPointer {
     Internal(usize),
     Leaf(usize),
-}
\ No newline at end of file
+}
+
+struct SahSplit {
+    axis: usize,
+    count: usize,
+    cost: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface::{Material, Sphere};
+    use glam::Vec3A;
+
+    fn cluster(center: Vec3A, material: &Material) -> Vec<CanHit> {
+        (0..4)
+            .map(|i| {
+                CanHit::Sphere(Sphere {
+                    origin: center + Vec3A::new(i as f32 * 0.1, 0.0, 0.0),
+                    radius: 0.1,
+                    material,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sah_split_keeps_separated_clusters_apart() {
+        let material = Material {
+            k_ambient: Vec3A::splat(0.7),
+            k_diffuse: Vec3A::splat(0.7),
+            k_specular: Vec3A::splat(0.7),
+            k_reflective: Vec3A::splat(0.7),
+            shininess: 20.0,
+            emission: Vec3A::ZERO,
+        };
+        let mut surfaces = cluster(Vec3A::new(100.0, 0.0, 0.0), &material);
+        surfaces.extend(cluster(Vec3A::new(-100.0, 0.0, 0.0), &material));
+
+        let bvh = BVH::new(&mut surfaces);
+
+        // Two tight, far-apart clusters should split apart rather than collapse
+        // into one leaf covering both (and dragging in the empty space, and
+        // the world origin, between them) the way the unpatched binning did.
+        assert!(
+            bvh.leaf.len() > 1,
+            "expected separated clusters to split into multiple leaves, got {}",
+            bvh.leaf.len()
+        );
+        for leaf in &bvh.leaf {
+            assert!(
+                leaf.bounding.surface_area() < 10.0,
+                "leaf bounding box is too large, likely swallowed the gap between clusters"
+            );
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::f32::consts::PI;
+
+use glam::Vec3A;
+use rand::Rng;
+
+use crate::Ray;
+
+// A thin-lens camera: a pinhole perspective projection plus a finite aperture
+// that defocuses geometry away from focus_dist.
+pub struct Camera {
+    origin: Vec3A,
+
+    // basis and viewport geometry derived from the constructor args, cached so
+    // get_ray doesn't redo this work for every sample
+    u: Vec3A,
+    v: Vec3A,
+    lens_radius: f32,
+    horizontal: Vec3A,
+    vertical: Vec3A,
+    lower_left_corner: Vec3A,
+}
+impl Camera {
+    pub fn new(
+        origin: Vec3A,
+        look_at: Vec3A,
+        up: Vec3A,
+        vfov_degrees: f32,
+        aspect_ratio: f32,
+        aperture: f32,
+        focus_dist: f32,
+    ) -> Camera {
+        let theta = vfov_degrees.to_radians();
+        let viewport_height = 2.0 * (theta / 2.0).tan();
+        let viewport_width = aspect_ratio * viewport_height;
+
+        let forward = (look_at - origin).normalize();
+        let u = up.cross(forward).normalize();
+        let v = forward.cross(u);
+
+        let horizontal = focus_dist * viewport_width * u;
+        let vertical = focus_dist * viewport_height * v;
+        let lower_left_corner = origin - horizontal * 0.5 - vertical * 0.5 + focus_dist * forward;
+
+        Camera {
+            origin,
+            u,
+            v,
+            lens_radius: aperture * 0.5,
+            horizontal,
+            vertical,
+            lower_left_corner,
+        }
+    }
+
+    pub fn origin(&self) -> Vec3A {
+        self.origin
+    }
+
+    pub fn get_ray(&self, s: f32, t: f32, rng: &mut impl Rng) -> Ray {
+        let lens_point = Camera::sample_lens_disk(self.lens_radius, rng);
+        let offset = self.u * lens_point.x + self.v * lens_point.y;
+
+        let origin = self.origin + offset;
+        let direction = self.lower_left_corner + s * self.horizontal + t * self.vertical - origin;
+        Ray::new(origin, direction)
+    }
+
+    fn sample_lens_disk(radius: f32, rng: &mut impl Rng) -> Vec3A {
+        let u1: f32 = rng.gen();
+        let u2: f32 = rng.gen();
+        let r = radius * u1.sqrt();
+        let theta = 2.0 * PI * u2;
+        Vec3A::new(r * theta.cos(), r * theta.sin(), 0.0)
+    }
+}
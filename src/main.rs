@@ -1,4 +1,5 @@
 mod bvh;
+mod camera;
 mod surface;
 
 use std::collections::VecDeque;
@@ -10,12 +11,13 @@ use std::{io, thread};
 use std::io::{BufRead, Write};
 
 use bvh::BVH;
+use camera::Camera;
 use glam::Vec3A;
 use rand::Rng;
 use rayon::prelude::*;
 use surface::{CanHit, Geometry};
 
-use crate::surface::{Material, Sphere, Triangle};
+use crate::surface::{Material, SdfObject, Sphere, Torus, Triangle};
 
 struct Image {
     width: usize,
@@ -46,24 +48,85 @@ impl Image {
 pub struct Ray {
     origin: Vec3A,
     direction: Vec3A,
+    inv_direction: Vec3A,
+    // 1 where the corresponding inv_direction component is negative, 0 otherwise;
+    // indexes AABB::bounds so the slab test avoids a branch per axis.
+    signs: [usize; 3],
 }
 impl Ray {
     fn new(origin: Vec3A, direction: Vec3A) -> Ray {
-        Ray { origin, direction }
+        let inv_direction = Vec3A::ONE / direction;
+        let signs = [
+            (inv_direction.x < 0.0) as usize,
+            (inv_direction.y < 0.0) as usize,
+            (inv_direction.z < 0.0) as usize,
+        ];
+        Ray {
+            origin,
+            direction,
+            inv_direction,
+            signs,
+        }
     }
 }
 
-struct Light {
+struct PointLight {
     origin: Vec3A,
     diffuse_color: Vec3A,
     specular_color: Vec3A,
 }
 
+// a rectangular area light spanned by edge1/edge2 from corner
+struct RectLight {
+    corner: Vec3A,
+    edge1: Vec3A,
+    edge2: Vec3A,
+    diffuse_color: Vec3A,
+    specular_color: Vec3A,
+}
+
+enum Light {
+    Point(PointLight),
+    Rect(RectLight),
+}
+impl Light {
+    // for an area light, its centroid - not a shadow sample point
+    fn center(&self) -> Vec3A {
+        match self {
+            Light::Point(p) => p.origin,
+            Light::Rect(r) => r.corner + 0.5 * r.edge1 + 0.5 * r.edge2,
+        }
+    }
+
+    // a single shadow-ray sample point; the fixed origin for a point light
+    fn sample_point(&self, rng: &mut impl Rng) -> Vec3A {
+        match self {
+            Light::Point(p) => p.origin,
+            Light::Rect(r) => r.corner + rng.gen::<f32>() * r.edge1 + rng.gen::<f32>() * r.edge2,
+        }
+    }
+
+    fn diffuse_color(&self) -> Vec3A {
+        match self {
+            Light::Point(p) => p.diffuse_color,
+            Light::Rect(r) => r.diffuse_color,
+        }
+    }
+
+    fn specular_color(&self) -> Vec3A {
+        match self {
+            Light::Point(p) => p.specular_color,
+            Light::Rect(r) => r.specular_color,
+        }
+    }
+}
+
 struct SceneBuilder<'m> {
     surfaces: Vec<CanHit<'m>>,
     lights: Vec<Light>,
     global_light: Vec3A,
     camera: Vec3A,
+    shadow_samples: usize,
 }
 impl<'m> SceneBuilder<'m> {
     pub fn build<'a>(&'a mut self) -> Scene<'a, 'm> {
@@ -72,6 +135,7 @@ impl<'m> SceneBuilder<'m> {
             lights: &self.lights,
             global_light: self.global_light,
             camera: self.camera,
+            shadow_samples: self.shadow_samples,
         }
     }
     pub fn add_quad(&mut self, v0: Vec3A, v1: Vec3A, v2: Vec3A, v3: Vec3A, material: &'m Material) {
@@ -86,6 +150,7 @@ struct Scene<'a, 'm> {
     lights: &'a [Light],
     global_light: Vec3A,
     camera: Vec3A,
+    shadow_samples: usize,
 }
 impl<'a, 'm> Scene<'a, 'm>
 where
@@ -95,7 +160,7 @@ where
         self.bvh.hits_any(ray)
     }
 
-    pub fn best_hit(&'a self, ray: &Ray) -> Option<(f32, &dyn Geometry)> {
+    pub fn best_hit(&'a self, ray: &Ray) -> Option<(f32, f32, f32, &dyn Geometry)> {
         self.bvh.ray_intersect(ray)
     }
 
@@ -104,13 +169,14 @@ where
         if depth <= 0 {
             return color;
         }
-        if let Some((t, surface)) = self.best_hit(ray) {
+        if let Some((t, bary_u, bary_v, surface)) = self.best_hit(ray) {
             color += self.global_light * surface.material().k_ambient;
-            let hit = surface.hit(ray, t);
+            let hit = surface.hit(ray, t, bary_u, bary_v);
             let p = hit.at;
             let n = hit.surface_normal;
+            let mut rng = rand::thread_rng();
             for light in self.lights.iter() {
-                let l_v = (light.origin - p).normalize();
+                let l_v = (light.center() - p).normalize();
                 let v = (self.camera - p).normalize();
                 let view_reflection = (2.0 * (n.dot(v) * n)) - v;
 
@@ -119,17 +185,96 @@ where
 
                 let d = l_v.dot(n);
 
-                if d > 0.0 && !self.hits_any(&Ray::new(p, l_v)) {
-                    let lr = (2.0 * (n.dot(l_v)) * n) - l_v;
-                    color += surface.material().k_diffuse * d * light.diffuse_color;
-                    color += surface.material().k_specular
-                        * v.dot(lr).powf(surface.material().shininess)
-                        * light.specular_color;
+                if d > 0.0 {
+                    let shadow_samples = self.shadow_samples.max(1);
+                    let unoccluded = (0..shadow_samples)
+                        .filter(|_| {
+                            let sample = light.sample_point(&mut rng);
+                            let dist = (sample - p).length();
+                            // only hits short of the sample point occlude it, so
+                            // geometry lying on the light itself does not
+                            match self.best_hit(&Ray::new(p, (sample - p) / dist)) {
+                                Some((t, _, _, _)) => t >= dist * (1.0 - 1e-3),
+                                None => true,
+                            }
+                        })
+                        .count();
+                    let visibility = unoccluded as f32 / shadow_samples as f32;
+
+                    if visibility > 0.0 {
+                        let lr = (2.0 * (n.dot(l_v)) * n) - l_v;
+                        color += visibility * surface.material().k_diffuse * d * light.diffuse_color();
+                        color += visibility
+                            * surface.material().k_specular
+                            * v.dot(lr).powf(surface.material().shininess)
+                            * light.specular_color();
+                    }
                 }
             }
         }
         color
     }
+
+    // Monte Carlo path tracer with Russian-roulette termination, alongside
+    // the Whitted ray_color above.
+    pub fn path_color(&'a self, ray: &Ray, max_depth: usize, depth: usize) -> Vec3A {
+        if depth == 0 {
+            return Vec3A::ZERO;
+        }
+        let Some((t, bary_u, bary_v, surface)) = self.best_hit(ray) else {
+            return Vec3A::ZERO;
+        };
+
+        let material = surface.material();
+        if material.emission != Vec3A::ZERO {
+            return material.emission;
+        }
+
+        let hit = surface.hit(ray, t, bary_u, bary_v);
+        let p = hit.at;
+        let n = hit.surface_normal;
+
+        let mut rng = rand::thread_rng();
+        let p_specular = material.k_reflective.max_element().clamp(0.0, 1.0);
+
+        let (bounce_dir, mut throughput) = if rng.gen::<f32>() < p_specular {
+            let v = -ray.direction.normalize();
+            let mirror_dir = (2.0 * (n.dot(v) * n)) - v;
+            (mirror_dir, material.k_reflective / p_specular)
+        } else {
+            let dir = cosine_sample_hemisphere(n, &mut rng);
+            (dir, material.k_diffuse / (1.0 - p_specular))
+        };
+
+        const RR_START_DEPTH: usize = 3;
+        let bounces_taken = max_depth - depth;
+        if bounces_taken >= RR_START_DEPTH {
+            let p_continue = throughput.max_element().clamp(0.0, 1.0);
+            if p_continue <= 0.0 || rng.gen::<f32>() > p_continue {
+                return Vec3A::ZERO;
+            }
+            throughput /= p_continue;
+        }
+
+        throughput * self.path_color(&Ray::new(p, bounce_dir), max_depth, depth - 1)
+    }
+}
+
+// Samples a direction on the cosine-weighted hemisphere around n.
+fn cosine_sample_hemisphere(n: Vec3A, rng: &mut impl Rng) -> Vec3A {
+    let u1: f32 = rng.gen();
+    let u2: f32 = rng.gen();
+    let r = u1.sqrt();
+    let theta = 2.0 * std::f32::consts::PI * u2;
+    let x = r * theta.cos();
+    let y = r * theta.sin();
+    let z = (1.0 - r * r).max(0.0).sqrt();
+
+    let tangent = if n.x.abs() > 0.9 { Vec3A::Y } else { Vec3A::X };
+    let t = n.cross(tangent).normalize();
+    let b = n.cross(t);
+
+    x * t + y * b + z * n
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -141,14 +286,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let w = image.width;
     let fh = image.height as f32;
     let fw = image.width as f32;
-    let aspect_ratio = fh / fw;
 
-    let top_left = Vec3A::new(-1.0, aspect_ratio, 0.0);
-    let top_right = Vec3A::new(1.0, aspect_ratio, 0.0);
-    let bottom_left = Vec3A::new(-1.0, -aspect_ratio, 0.0);
-    let bottom_right = Vec3A::new(1.0, -aspect_ratio, 0.0);
-
-    let camera = Vec3A::new(0.0, 0.0, -1.0);
+    let camera = Camera::new(
+        Vec3A::new(0.0, 0.0, -1.0),
+        Vec3A::new(0.0, 0.0, 1.0),
+        Vec3A::Y,
+        90.0,
+        fw / fh,
+        0.0,
+        1.0,
+    );
 
     let red = Material {
         k_ambient: Vec3A::new(1.0, 0.0, 0.0),
@@ -156,6 +303,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         k_reflective: Vec3A::splat(0.2),
         k_specular: Vec3A::splat(0.1),
         shininess: 20.0,
+        emission: Vec3A::ZERO,
     };
 
     let green = Material {
@@ -164,6 +312,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         k_reflective: Vec3A::splat(0.2),
         k_specular: Vec3A::splat(0.1),
         shininess: 20.0,
+        emission: Vec3A::ZERO,
     };
 
     let blue = Material {
@@ -172,7 +321,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         k_reflective: Vec3A::splat(0.2),
         k_specular: Vec3A::splat(0.1),
         shininess: 20.0,
+        emission: Vec3A::ZERO,
     };
+    // shadow rays taken per light per hit, alongside the `samples`/`ray_depth`
+    // knobs below; higher values resolve softer penumbrae on the area light
+    let shadow_samples = 8;
+    let rect_light_corner = Vec3A::new(-2.0, 8.0, 10.0);
+    let rect_light_edge1 = Vec3A::new(2.0, 0.0, 0.0);
+    let rect_light_edge2 = Vec3A::new(0.0, 0.0, 2.0);
     let mut builder = SceneBuilder {
         surfaces: vec![
             CanHit::Sphere(Sphere {
@@ -192,19 +348,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }),
         ],
         lights: vec![
-            Light {
-                origin: Vec3A::new(-1.0, 8.0, 11.0),
+            Light::Rect(RectLight {
+                corner: rect_light_corner,
+                edge1: rect_light_edge1,
+                edge2: rect_light_edge2,
                 diffuse_color: 0.5 * Vec3A::new(1.0, 0.2, 1.0),
                 specular_color: Vec3A::splat(0.8),
-            },
-            Light {
+            }),
+            Light::Point(PointLight {
                 origin: Vec3A::new(9.0, 8.0, 5.0),
                 diffuse_color: 0.5 * Vec3A::new(0.0, 1.0, 0.0),
                 specular_color: Vec3A::splat(0.8),
-            },
+            }),
         ],
         global_light: Vec3A::new(0.5, 0.5, 0.5),
-        camera,
+        camera: camera.origin(),
+        shadow_samples,
     };
 
     let purple = Material {
@@ -213,6 +372,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         k_reflective: Vec3A::splat(0.1),
         k_specular: Vec3A::splat(0.1),
         shininess: 20.0,
+        emission: Vec3A::ZERO,
     };
 
     builder.add_quad(
@@ -223,47 +383,101 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &purple,
     );
 
+    // Gives the rect light above an actual emissive surface, so `path_color`
+    // (which only terminates on emission, not on `Light`) has something to find.
+    let rect_light_emitter = Material {
+        k_ambient: Vec3A::ZERO,
+        k_diffuse: Vec3A::ZERO,
+        k_reflective: Vec3A::ZERO,
+        k_specular: Vec3A::ZERO,
+        shininess: 1.0,
+        emission: 4.0 * Vec3A::new(1.0, 0.2, 1.0),
+    };
+    builder.add_quad(
+        rect_light_corner,
+        rect_light_corner + rect_light_edge1,
+        rect_light_corner + rect_light_edge1 + rect_light_edge2,
+        rect_light_corner + rect_light_edge2,
+        &rect_light_emitter,
+    );
+
+    let gold = Material {
+        k_ambient: Vec3A::new(0.7, 0.6, 0.2),
+        k_diffuse: Vec3A::new(0.5, 0.4, 0.1),
+        k_reflective: Vec3A::splat(0.3),
+        k_specular: Vec3A::splat(0.2),
+        shininess: 30.0,
+        emission: Vec3A::ZERO,
+    };
+    builder.surfaces.push(CanHit::Sdf(SdfObject::new(
+        Box::new(Torus {
+            center: Vec3A::new(-1.0, -0.5, 6.0),
+            major_radius: 1.0,
+            minor_radius: 0.35,
+        }),
+        &gold,
+    )));
+
     let load = Instant::now();
     let teapot = File::open("teapot.obj")?;
     let mut vertices: Vec<Vec3A> = vec![];
+    let mut faces: Vec<[usize; 3]> = vec![];
     let material = Material {
         k_ambient: Vec3A::new(0.7, 0.3, 0.7),
         k_diffuse: Vec3A::new(0.5, 0.5, 0.7),
         k_reflective: Vec3A::splat(0.1),
         k_specular: Vec3A::splat(0.1),
         shininess: 20.0,
+        emission: Vec3A::ZERO,
     };
     let offset = Vec3A::new(0.0, 0.0, 10.0);
     for line in io::BufReader::new(teapot).lines() {
         let line = line?;
-        match line.chars().next() {
-            Some('v') => {
-                let nums = line
-                    .split(' ')
-                    .skip(1)
-                    .map(|s| s.parse::<f32>())
-                    .flatten()
-                    .collect::<Vec<f32>>();
-                vertices.push(Vec3A::from_slice(&nums) + offset);
-            }
-            Some('f') => {
-                let nums = line
-                    .split(' ')
-                    .skip(1)
-                    .map(|s| s.parse::<usize>())
-                    .flatten()
-                    .collect::<Vec<usize>>();
-                /*
-                builder.surfaces.push(CanHit::Triangle(Triangle::new(
-                    vertices[nums[0] - 1],
-                    vertices[nums[1] - 1],
-                    vertices[nums[2] - 1],
-                    &material,
-                )));*/
-            }
-            _ => (),
+        if line.starts_with("v ") {
+            let nums = line
+                .split(' ')
+                .skip(1)
+                .map(|s| s.parse::<f32>())
+                .flatten()
+                .collect::<Vec<f32>>();
+            vertices.push(Vec3A::from_slice(&nums) + offset);
+        } else if line.starts_with("f ") {
+            // faces may reference a texture/normal index per vertex ("a//na"); we
+            // only need the vertex index, our own smooth normals are averaged below
+            let idxs = line
+                .split(' ')
+                .skip(1)
+                .map(|s| s.split('/').next().unwrap().parse::<usize>())
+                .flatten()
+                .collect::<Vec<usize>>();
+            faces.push([idxs[0] - 1, idxs[1] - 1, idxs[2] - 1]);
         }
     }
+
+    let mut vertex_normals = vec![Vec3A::ZERO; vertices.len()];
+    for face in faces.iter() {
+        let [a, b, c] = *face;
+        let face_normal = (vertices[b] - vertices[a]).cross(vertices[c] - vertices[a]);
+        vertex_normals[a] += face_normal;
+        vertex_normals[b] += face_normal;
+        vertex_normals[c] += face_normal;
+    }
+    for normal in vertex_normals.iter_mut() {
+        *normal = normal.normalize();
+    }
+
+    for face in faces.iter() {
+        let [a, b, c] = *face;
+        builder.surfaces.push(CanHit::Triangle(Triangle::new_smooth(
+            vertices[a],
+            vertices[b],
+            vertices[c],
+            vertex_normals[a],
+            vertex_normals[b],
+            vertex_normals[c],
+            &material,
+        )));
+    }
     println!("Finished load in: {} ms", load.elapsed().as_millis());
 
     let scene = builder.build();
@@ -300,6 +514,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let samples = 10;
     let ray_depth = 10;
+    let path_trace = std::env::args().any(|arg| arg == "--path-trace");
     image
         .pixels
         .par_iter_mut()
@@ -310,16 +525,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             let mut rng = rand::thread_rng();
             for _ in 0..samples {
-                let xt = (x as f32 + rng.gen::<f32>()) / (fw - 1.0);
-                let yt = (y as f32 + rng.gen::<f32>()) / (fh - 1.0);
-
-                let t = top_left.lerp(top_right, xt);
-                let b = bottom_left.lerp(bottom_right, xt);
-                let p = t.lerp(b, yt);
+                let s = (x as f32 + rng.gen::<f32>()) / (fw - 1.0);
+                let t = 1.0 - (y as f32 + rng.gen::<f32>()) / (fh - 1.0);
 
-                let ray = Ray::new(p, p - camera);
+                let ray = camera.get_ray(s, t, &mut rng);
 
-                *pixel += scene.ray_color(&ray, ray_depth);
+                *pixel += if path_trace {
+                    scene.path_color(&ray, ray_depth, ray_depth)
+                } else {
+                    scene.ray_color(&ray, ray_depth)
+                };
             }
             *pixel /= samples as f32;
             rayon_counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
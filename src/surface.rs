@@ -1,5 +1,3 @@
-use std::f32::{INFINITY, NEG_INFINITY};
-
 use glam::Vec3A;
 
 use crate::{bvh::BVH, Ray};
@@ -8,12 +6,15 @@ use crate::{bvh::BVH, Ray};
 pub enum CanHit<'m> {
     Sphere(Sphere<'m>),
     Triangle(Triangle<'m>),
+    Sdf(SdfObject<'m>),
 }
 impl<'m> CanHit<'m> {
-    pub fn ray_intersect(&self, ray: &Ray) -> Option<(f32, &dyn Geometry)> {
+    // (t, barycentric u, v, surface); u/v are 0.0 for shapes without them
+    pub fn ray_intersect(&self, ray: &Ray) -> Option<(f32, f32, f32, &dyn Geometry)> {
         match self {
             CanHit::Sphere(s) => s.ray_intersect(ray),
             CanHit::Triangle(t) => t.ray_intersect(ray),
+            CanHit::Sdf(s) => s.ray_intersect(ray),
         }
     }
     pub fn hits_any(&self, ray: &Ray) -> bool {
@@ -23,56 +24,76 @@ impl<'m> CanHit<'m> {
         match self {
             CanHit::Sphere(s) => s.aabb(),
             CanHit::Triangle(t) => t.aabb(),
+            CanHit::Sdf(s) => s.aabb(),
         }
     }
 }
 pub trait Geometry<'m> {
     fn material(&self) -> &'m Material;
-    fn hit(&self, ray: &Ray, t: f32) -> Hit;
+    fn hit(&self, ray: &Ray, t: f32, u: f32, v: f32) -> Hit;
 }
 #[derive(Debug, Clone)]
 pub struct AABB {
-    min: Vec3A,
-    max: Vec3A,
+    // indexed by a ray's per-axis sign, so `ray_hit` can avoid a branch per axis
+    bounds: [Vec3A; 2],
 }
 impl AABB {
-    pub fn ray_hit(&self, ray: &Ray) -> bool {
-        let ta = (self.min - ray.origin) / ray.direction;
-        let tb = (self.max - ray.origin) / ray.direction;
-        let min_t = ta.min(tb);
-        let max_t = ta.max(tb);
+    pub fn new(min: Vec3A, max: Vec3A) -> AABB {
+        AABB { bounds: [min, max] }
+    }
 
-        min_t.max_element() < max_t.min_element()
+    // slab test against the ray's precomputed inverse direction and signs
+    pub fn ray_hit(&self, ray: &Ray) -> Option<f32> {
+        let mut t_min = (self.bounds[ray.signs[0]].x - ray.origin.x) * ray.inv_direction.x;
+        let mut t_max = (self.bounds[1 - ray.signs[0]].x - ray.origin.x) * ray.inv_direction.x;
+
+        for axis in 1..3 {
+            let near = (self.bounds[ray.signs[axis]][axis] - ray.origin[axis])
+                * ray.inv_direction[axis];
+            let far = (self.bounds[1 - ray.signs[axis]][axis] - ray.origin[axis])
+                * ray.inv_direction[axis];
+            t_min = t_min.max(near);
+            t_max = t_max.min(far);
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        if t_max < 0.0 {
+            None
+        } else {
+            Some(t_min)
+        }
     }
 
     pub fn midpoint(&self) -> Vec3A {
-        (self.min + self.max) * 0.5
+        (self.bounds[0] + self.bounds[1]) * 0.5
+    }
+
+    pub fn min(&self) -> Vec3A {
+        self.bounds[0]
+    }
+
+    pub fn max(&self) -> Vec3A {
+        self.bounds[1]
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.bounds[1] - self.bounds[0];
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
     }
 
     pub fn zero() -> AABB {
         AABB {
-            min: Vec3A::ZERO,
-            max: Vec3A::ZERO,
+            bounds: [Vec3A::ZERO, Vec3A::ZERO],
         }
     }
 
     pub fn union_mut(&mut self, aabb: &AABB) {
-        self.min = self.min.min(aabb.min);
-        self.max = self.max.max(aabb.max);
-    }
-
-    pub fn max_axis(&self) -> usize {
-        let diff = self.max - self.min;
-        let mut max = -NEG_INFINITY;
-        let mut max_i = 0;
-        for i in 0..=2 {
-            if diff[i] > max {
-                max = diff[i];
-                max_i = i;
-            }
-        }
-        return max_i;
+        self.bounds[0] = self.bounds[0].min(aabb.bounds[0]);
+        self.bounds[1] = self.bounds[1].max(aabb.bounds[1]);
     }
+
 }
 
 #[derive(Debug, Clone)]
@@ -83,7 +104,7 @@ pub struct Sphere<'a> {
     pub material: &'a Material,
 }
 impl<'a> Sphere<'a> {
-    fn ray_intersect(&self, ray: &Ray) -> Option<(f32, &dyn Geometry)> {
+    fn ray_intersect(&self, ray: &Ray) -> Option<(f32, f32, f32, &dyn Geometry)> {
         let a = ray.direction.length_squared();
         let oc = ray.origin - self.origin;
         let half_b = oc.dot(ray.direction);
@@ -96,9 +117,9 @@ impl<'a> Sphere<'a> {
             let root_one = (-half_b - discriminant.sqrt()) / a;
             let root_two = (-half_b + discriminant.sqrt()) / a;
             if root_one > 1.0 {
-                Some((root_one, self))
+                Some((root_one, 0.0, 0.0, self))
             } else if root_two > 1.0 {
-                Some((root_two, self))
+                Some((root_two, 0.0, 0.0, self))
             } else {
                 None
             }
@@ -106,14 +127,14 @@ impl<'a> Sphere<'a> {
     }
 
     fn aabb(&self) -> AABB {
-        AABB {
-            min: self.origin - Vec3A::splat(self.radius),
-            max: self.origin + Vec3A::splat(self.radius),
-        }
+        AABB::new(
+            self.origin - Vec3A::splat(self.radius),
+            self.origin + Vec3A::splat(self.radius),
+        )
     }
 }
 impl<'m> Geometry<'m> for Sphere<'m> {
-    fn hit(&self, ray: &Ray, t: f32) -> Hit {
+    fn hit(&self, ray: &Ray, t: f32, _u: f32, _v: f32) -> Hit {
         let p = ray.origin + t * ray.direction;
         Hit {
             at: p,
@@ -133,6 +154,8 @@ pub struct Triangle<'m> {
     v2: Vec3A,
 
     normal: Vec3A,
+    // per-vertex normals for Phong/smooth shading; None falls back to the flat `normal`
+    vertex_normals: Option<(Vec3A, Vec3A, Vec3A)>,
 
     pub material: &'m Material,
 }
@@ -147,6 +170,30 @@ impl<'m> Triangle<'m> {
             v1,
             v2,
             normal,
+            vertex_normals: None,
+            material,
+        }
+    }
+
+    pub fn new_smooth(
+        v0: Vec3A,
+        v1: Vec3A,
+        v2: Vec3A,
+        n0: Vec3A,
+        n1: Vec3A,
+        n2: Vec3A,
+        material: &'m Material,
+    ) -> Triangle<'m> {
+        let a = v1 - v0;
+        let b = v2 - v0;
+        let normal = a.cross(b).normalize();
+
+        Triangle {
+            v0,
+            v1,
+            v2,
+            normal,
+            vertex_normals: Some((n0, n1, n2)),
             material,
         }
     }
@@ -154,7 +201,7 @@ impl<'m> Triangle<'m> {
 
 pub const EPSILON: f32 = 1e-6;
 impl<'m> Triangle<'m> {
-    fn ray_intersect(&self, ray: &Ray) -> Option<(f32, &dyn Geometry<'m>)> {
+    fn ray_intersect(&self, ray: &Ray) -> Option<(f32, f32, f32, &dyn Geometry<'m>)> {
         // if ray and plane of triangle are parallel, no intersection
         // Moller-Trumbore
         let v0v1 = self.v1 - self.v0;
@@ -187,22 +234,26 @@ impl<'m> Triangle<'m> {
         if t < EPSILON {
             None
         } else {
-            Some((t, self))
+            Some((t, u, v, self))
         }
     }
 
     fn aabb(&self) -> AABB {
-        AABB {
-            min: self.v0.min(self.v1.min(self.v2)),
-            max: self.v0.max(self.v1.max(self.v2)),
-        }
+        AABB::new(
+            self.v0.min(self.v1.min(self.v2)),
+            self.v0.max(self.v1.max(self.v2)),
+        )
     }
 }
 impl<'m> Geometry<'m> for Triangle<'m> {
-    fn hit(&self, ray: &Ray, t: f32) -> Hit {
+    fn hit(&self, ray: &Ray, t: f32, u: f32, v: f32) -> Hit {
+        let surface_normal = match self.vertex_normals {
+            Some((n0, n1, n2)) => ((1.0 - u - v) * n0 + u * n1 + v * n2).normalize(),
+            None => self.normal,
+        };
         Hit {
             at: ray.origin + t * ray.direction,
-            surface_normal: self.normal,
+            surface_normal,
         }
     }
 
@@ -212,6 +263,145 @@ impl<'m> Geometry<'m> for Triangle<'m> {
     }
 }
 
+// implicit surfaces rendered by marching a ray through the field, not by
+// solving for t directly
+pub trait DistanceField: Send + Sync {
+    fn distance(&self, p: Vec3A) -> f32;
+    fn aabb(&self) -> AABB;
+}
+
+// Lets `SdfObject` keep deriving `Debug` without requiring every shape to.
+impl std::fmt::Debug for dyn DistanceField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<distance field>")
+    }
+}
+
+#[derive(Debug)]
+pub struct Torus {
+    pub center: Vec3A,
+    pub major_radius: f32,
+    pub minor_radius: f32,
+}
+impl DistanceField for Torus {
+    fn distance(&self, p: Vec3A) -> f32 {
+        let p = p - self.center;
+        let q_x = (p.x * p.x + p.z * p.z).sqrt() - self.major_radius;
+        (q_x * q_x + p.y * p.y).sqrt() - self.minor_radius
+    }
+
+    fn aabb(&self) -> AABB {
+        let r = self.major_radius + self.minor_radius;
+        AABB::new(
+            self.center - Vec3A::new(r, self.minor_radius, r),
+            self.center + Vec3A::new(r, self.minor_radius, r),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct RoundedBox {
+    pub center: Vec3A,
+    pub half_extents: Vec3A,
+    pub rounding: f32,
+}
+impl DistanceField for RoundedBox {
+    fn distance(&self, p: Vec3A) -> f32 {
+        let q = (p - self.center).abs() - self.half_extents;
+        q.max(Vec3A::ZERO).length() + q.max_element().min(0.0) - self.rounding
+    }
+
+    fn aabb(&self) -> AABB {
+        let r = self.half_extents + Vec3A::splat(self.rounding);
+        AABB::new(self.center - r, self.center + r)
+    }
+}
+
+#[derive(Debug)]
+pub struct Plane {
+    pub normal: Vec3A,
+    pub d: f32,
+}
+impl DistanceField for Plane {
+    fn distance(&self, p: Vec3A) -> f32 {
+        p.dot(self.normal) - self.d
+    }
+
+    fn aabb(&self) -> AABB {
+        // An infinite plane has no true bounding box; clamp to a box large
+        // enough for any scene in this renderer so it can still sit in the BVH.
+        const HALF_EXTENT: f32 = 1e4;
+        AABB::new(Vec3A::splat(-HALF_EXTENT), Vec3A::splat(HALF_EXTENT))
+    }
+}
+
+const SDF_EPSILON: f32 = 1e-4;
+const SDF_NORMAL_EPSILON: f32 = 1e-4;
+const SDF_MAX_STEPS: usize = 128;
+const SDF_MAX_DISTANCE: f32 = 1e4;
+
+#[derive(Debug)]
+pub struct SdfObject<'m> {
+    shape: Box<dyn DistanceField>,
+    pub material: &'m Material,
+}
+impl<'m> SdfObject<'m> {
+    pub fn new(shape: Box<dyn DistanceField>, material: &'m Material) -> SdfObject<'m> {
+        SdfObject { shape, material }
+    }
+
+    fn ray_intersect(&self, ray: &Ray) -> Option<(f32, f32, f32, &dyn Geometry<'m>)> {
+        let dir_len = ray.direction.length();
+        let dir = ray.direction / dir_len;
+
+        let mut s = 0.0;
+        for _ in 0..SDF_MAX_STEPS {
+            let p = ray.origin + s * dir;
+            let d = self.shape.distance(p);
+            if d < SDF_EPSILON {
+                let t = s / dir_len;
+                return if t < EPSILON { None } else { Some((t, 0.0, 0.0, self)) };
+            }
+            s += d;
+            if s > SDF_MAX_DISTANCE {
+                return None;
+            }
+        }
+        None
+    }
+
+    fn aabb(&self) -> AABB {
+        self.shape.aabb()
+    }
+
+    // central-difference gradient of the distance field, which points along the surface normal
+    fn normal_at(&self, p: Vec3A) -> Vec3A {
+        let h = SDF_NORMAL_EPSILON;
+        let dx = Vec3A::new(h, 0.0, 0.0);
+        let dy = Vec3A::new(0.0, h, 0.0);
+        let dz = Vec3A::new(0.0, 0.0, h);
+        Vec3A::new(
+            self.shape.distance(p + dx) - self.shape.distance(p - dx),
+            self.shape.distance(p + dy) - self.shape.distance(p - dy),
+            self.shape.distance(p + dz) - self.shape.distance(p - dz),
+        )
+        .normalize()
+    }
+}
+impl<'m> Geometry<'m> for SdfObject<'m> {
+    fn hit(&self, ray: &Ray, t: f32, _u: f32, _v: f32) -> Hit {
+        let p = ray.origin + t * ray.direction;
+        Hit {
+            at: p,
+            surface_normal: self.normal_at(p),
+        }
+    }
+
+    fn material(&self) -> &'m Material {
+        &self.material
+    }
+}
+
 pub struct Hit {
     pub at: Vec3A,
     pub surface_normal: Vec3A,
@@ -224,6 +414,8 @@ pub struct Material {
     pub k_specular: Vec3A,
     pub k_reflective: Vec3A,
     pub shininess: f32,
+    // non-zero for light-emitting geometry; path_color terminates on hit
+    pub emission: Vec3A,
 }
 impl Material {
     fn default() -> Material {
@@ -233,6 +425,7 @@ impl Material {
             k_specular: Vec3A::splat(0.7),
             k_reflective: Vec3A::splat(0.7),
             shininess: 20.0,
+            emission: Vec3A::ZERO,
         }
     }
 }
@@ -245,7 +438,7 @@ mod tests {
 
     use crate::{surface::AABB, Ray};
 
-    use super::{CanHit, Material, Triangle};
+    use super::{CanHit, DistanceField, Material, Plane, RoundedBox, Triangle};
 
     fn assert_approx_ex(a: f32, b: f32, msg: &'static str) {
         println!("{} ~= {}? a - b: {}", a, b, (a - b).abs());
@@ -278,12 +471,47 @@ mod tests {
 
     #[test]
     fn test_aabb_intersection() {
-        let aabb = AABB {
-            min: Vec3A::splat(-1.0),
-            max: Vec3A::splat(1.0),
+        let aabb = AABB::new(Vec3A::splat(-1.0), Vec3A::splat(1.0));
+
+        assert!(aabb
+            .ray_hit(&Ray::new(Vec3A::new(0.0, 0.0, -2.0), Vec3A::Z))
+            .is_some());
+        assert!(aabb
+            .ray_hit(&Ray::new(Vec3A::new(2.0, 0.0, -2.0), Vec3A::Z))
+            .is_none());
+    }
+
+    #[test]
+    fn test_rounded_box_distance_field() {
+        let rounded_box = RoundedBox {
+            center: Vec3A::ZERO,
+            half_extents: Vec3A::splat(1.0),
+            rounding: 0.2,
+        };
+
+        assert_approx_ex(
+            rounded_box.distance(Vec3A::ZERO),
+            -1.2,
+            "distance at center",
+        );
+        assert!(
+            rounded_box.distance(Vec3A::new(1.2, 0.0, 0.0)).abs() < 1e-6,
+            "expected distance near zero on the rounded surface"
+        );
+
+        let aabb = rounded_box.aabb();
+        assert_eq!(aabb.min(), Vec3A::splat(-1.2));
+        assert_eq!(aabb.max(), Vec3A::splat(1.2));
+    }
+
+    #[test]
+    fn test_plane_distance_field() {
+        let plane = Plane {
+            normal: Vec3A::Y,
+            d: 2.0,
         };
 
-        assert!(aabb.ray_hit(&Ray::new(Vec3A::new(0.0, 0.0, -2.0), Vec3A::Z)));
-        assert!(!aabb.ray_hit(&Ray::new(Vec3A::new(2.0, 0.0, -2.0), Vec3A::Z)));
+        assert_approx_ex(plane.distance(Vec3A::new(0.0, 2.0, 0.0)), 0.0, "on the plane");
+        assert_approx_ex(plane.distance(Vec3A::new(5.0, 5.0, 5.0)), 3.0, "above the plane");
     }
 }